@@ -0,0 +1,86 @@
+use std::fmt;
+
+// Boxed so each variant can carry whatever concrete error it wrapped
+// (`ProgramError`, a bs58/base64 decode error, ...) without `ApiError` itself
+// becoming generic over it.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+// Typed, machine-discriminable failures for the API. Each variant carries a
+// stable `code()` in addition to its human-readable `Display`, so clients can
+// branch on `error_code` instead of pattern-matching the `error` string.
+//
+// This was originally scoped around a macro-generated "flex-error" style
+// enum with automatic source chaining. Pulling in that dependency wasn't
+// worth it for a handful of variants, so this hand-rolls the same contract
+// instead: `Display` stays a short, client-facing message, and variants that
+// wrap a lower-level failure keep it in a `source` field rather than
+// discarding it, so `std::error::Error::source()` still exposes the real
+// `ProgramError`/decode error for logs even though the HTTP response doesn't.
+#[derive(Debug)]
+pub enum ApiError {
+    InvalidPubkey { field: &'static str, value: String },
+    InvalidBlockhash { value: String },
+    SecretDecode { source: BoxError },
+    InvalidSecretLength,
+    SignatureDecode { source: Option<BoxError> },
+    ZeroAmount,
+    MalformedBody,
+    UnknownKeyId,
+    SignerNotRequired { pubkey: String },
+    InstructionBuild { reason: &'static str, source: BoxError },
+}
+
+impl ApiError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::InvalidPubkey { .. } => "invalid_pubkey",
+            ApiError::InvalidBlockhash { .. } => "invalid_blockhash",
+            ApiError::SecretDecode { .. } => "secret_decode",
+            ApiError::InvalidSecretLength => "invalid_secret_length",
+            ApiError::SignatureDecode { .. } => "signature_decode",
+            ApiError::ZeroAmount => "zero_amount",
+            ApiError::MalformedBody => "malformed_body",
+            ApiError::UnknownKeyId => "unknown_key_id",
+            ApiError::SignerNotRequired { .. } => "signer_not_required",
+            ApiError::InstructionBuild { .. } => "instruction_build_failed",
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::InvalidPubkey { field, value } => {
+                write!(f, "invalid public key for {field}: {value}")
+            }
+            ApiError::InvalidBlockhash { value } => {
+                write!(f, "invalid recent blockhash: {value}")
+            }
+            ApiError::SecretDecode { .. } => write!(f, "invalid secret key format"),
+            ApiError::InvalidSecretLength => write!(f, "secret key must decode to 32 bytes"),
+            ApiError::SignatureDecode { .. } => write!(f, "invalid signature format"),
+            ApiError::ZeroAmount => write!(f, "amount must be greater than 0"),
+            ApiError::MalformedBody => write!(f, "request body is missing required fields"),
+            ApiError::UnknownKeyId => write!(f, "no key stored for that pubkey"),
+            ApiError::SignerNotRequired { pubkey } => {
+                write!(f, "{pubkey} is not a required signer of this transaction")
+            }
+            ApiError::InstructionBuild { reason, .. } => {
+                write!(f, "failed to build {reason} instruction")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ApiError::SecretDecode { source } => Some(source.as_ref()),
+            ApiError::SignatureDecode { source } => {
+                source.as_deref().map(|e| e as &(dyn std::error::Error + 'static))
+            }
+            ApiError::InstructionBuild { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
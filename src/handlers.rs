@@ -1,14 +1,125 @@
-use axum::{extract::Json, response::Json as ResponseJson};
+use axum::{
+    extract::{Json, State},
+    response::Json as ResponseJson,
+};
 use ed25519_dalek::{Signer, Verifier, SigningKey, VerifyingKey, Signature};
 use rand::rngs::OsRng;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature as SolanaSignature;
+use solana_sdk::transaction::Transaction;
 use std::str::FromStr;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 
+use crate::errors::ApiError;
+use crate::keystore::AppState;
 use crate::types::*;
 
-const SPL_TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+const METADATA_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+
+// Derives an owner's associated token account for a mint. This is a PDA off
+// the ATA program, not the token program, so it's kept as a shared helper
+// rather than re-deriving the seeds at each call site.
+fn derive_associated_token_account(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), spl_token::id().as_ref(), mint.as_ref()],
+        &spl_associated_token_account::id(),
+    )
+    .0
+}
+
+// Derives the Metaplex metadata PDA for a mint.
+fn derive_metadata_account(metadata_program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", metadata_program_id.as_ref(), mint.as_ref()],
+        metadata_program_id,
+    )
+    .0
+}
+
+// Derives the Metaplex master edition PDA for a mint.
+fn derive_master_edition_account(metadata_program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            metadata_program_id.as_ref(),
+            mint.as_ref(),
+            b"edition",
+        ],
+        metadata_program_id,
+    )
+    .0
+}
+
+// Finds the slot `pubkey` occupies among a message's account keys, which is
+// also the index its signature belongs at in `Transaction::signatures`.
+// Returns `None` if the pubkey isn't a signer on this message at all, or if
+// its slot falls outside the signer prefix of `account_keys`.
+fn find_signer_index(message: &Message, pubkey: &Pubkey) -> Option<usize> {
+    let num_signers = message.header.num_required_signatures as usize;
+    message
+        .account_keys
+        .iter()
+        .position(|key| key == pubkey)
+        .filter(|&index| index < num_signers)
+}
+
+// Converts a real `solana_sdk` instruction into our wire-format response,
+// so every handler that builds one goes through the same serialization path.
+fn instruction_to_response(ix: Instruction) -> InstructionResponse {
+    InstructionResponse {
+        program_id: ix.program_id.to_string(),
+        accounts: ix
+            .accounts
+            .into_iter()
+            .map(|meta| AccountMeta {
+                pubkey: meta.pubkey.to_string(),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        instruction_data: BASE64.encode(ix.data),
+    }
+}
+
+// Converts a caller-supplied instruction spec (the same shape we hand back
+// from the instruction-building endpoints) into a real `solana_sdk` instruction.
+fn instruction_response_to_instruction(spec: InstructionResponse) -> Result<Instruction, ApiError> {
+    let program_id = Pubkey::from_str(&spec.program_id).map_err(|_| ApiError::InvalidPubkey {
+        field: "program_id",
+        value: spec.program_id.clone(),
+    })?;
+
+    let accounts = spec
+        .accounts
+        .into_iter()
+        .map(|meta| {
+            Pubkey::from_str(&meta.pubkey)
+                .map(|pubkey| solana_sdk::instruction::AccountMeta {
+                    pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .map_err(|_| ApiError::InvalidPubkey {
+                    field: "accounts.pubkey",
+                    value: meta.pubkey.clone(),
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let data = BASE64
+        .decode(&spec.instruction_data)
+        .map_err(|_| ApiError::MalformedBody)?;
+
+    Ok(Instruction {
+        program_id,
+        accounts,
+        data,
+    })
+}
 
 pub async fn generate_keypair() -> ResponseJson<ApiResponse<KeypairResponse>> {
     let signing_key = SigningKey::generate(&mut OsRng);
@@ -26,46 +137,49 @@ pub async fn create_token(
 ) -> ResponseJson<ApiResponse<InstructionResponse>> {
     let req = match payload {
         Ok(Json(req)) => req,
-        Err(_) => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
+        Err(_) => return ResponseJson(ApiResponse::error(ApiError::MalformedBody)),
     };
     // Validate inputs
-    let _mint_authority = match Pubkey::from_str(&req.mint_authority) {
+    let mint_authority = match Pubkey::from_str(&req.mint_authority) {
         Ok(pk) => pk,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid mint authority public key".to_string())),
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                // Matches the wire field name ("mintAuthority"), not the
+                // Rust field name, so callers can map the error back to
+                // the key they actually sent.
+                field: "mintAuthority",
+                value: req.mint_authority,
+            }))
+        }
     };
-    
+
     let mint = match Pubkey::from_str(&req.mint) {
         Ok(pk) => pk,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid mint public key".to_string())),
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "mint",
+                value: req.mint,
+            }))
+        }
     };
 
-    // Create mock instruction for initialize mint
-    let accounts = vec![
-        AccountMeta {
-            pubkey: mint.to_string(),
-            is_signer: false,
-            is_writable: true,
-        },
-        AccountMeta {
-            pubkey: "SysvarRent111111111111111111111111111111111".to_string(),
-            is_signer: false,
-            is_writable: false,
-        },
-    ];
-
-    // Mock instruction data for InitializeMint
-    let instruction_data = BASE64.encode([
-        0, // InitializeMint instruction index
+    let ix = match spl_token::instruction::initialize_mint(
+        &spl_token::id(),
+        &mint,
+        &mint_authority,
+        Some(&mint_authority),
         req.decimals,
-    ]);
-
-    let response = InstructionResponse {
-        program_id: SPL_TOKEN_PROGRAM_ID.to_string(),
-        accounts,
-        instruction_data,
+    ) {
+        Ok(ix) => ix,
+        Err(e) => {
+            return ResponseJson(ApiResponse::error(ApiError::InstructionBuild {
+                reason: "initialize_mint",
+                source: Box::new(e),
+            }))
+        }
     };
 
-    ResponseJson(ApiResponse::success(response))
+    ResponseJson(ApiResponse::success(instruction_to_response(ix)))
 }
 
 pub async fn mint_token(
@@ -73,53 +187,193 @@ pub async fn mint_token(
 ) -> ResponseJson<ApiResponse<InstructionResponse>> {
     let req = match payload {
         Ok(Json(req)) => req,
-        Err(_) => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
+        Err(_) => return ResponseJson(ApiResponse::error(ApiError::MalformedBody)),
     };
     // Validate inputs
     let mint = match Pubkey::from_str(&req.mint) {
         Ok(pk) => pk,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid mint public key".to_string())),
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "mint",
+                value: req.mint,
+            }))
+        }
     };
-    
+
     let destination = match Pubkey::from_str(&req.destination) {
         Ok(pk) => pk,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid destination public key".to_string())),
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "destination",
+                value: req.destination,
+            }))
+        }
     };
-    
+
     let authority = match Pubkey::from_str(&req.authority) {
         Ok(pk) => pk,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid authority public key".to_string())),
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "authority",
+                value: req.authority,
+            }))
+        }
     };
 
-    let accounts = vec![
-        AccountMeta {
-            pubkey: destination.to_string(),
-            is_signer: false,
-            is_writable: true,
-        },
-        AccountMeta {
-            pubkey: mint.to_string(),
-            is_signer: false,
-            is_writable: true,
-        },
-        AccountMeta {
-            pubkey: authority.to_string(),
-            is_signer: true,
-            is_writable: false,
-        },
-    ];
+    let ix = match spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &mint,
+        &destination,
+        &authority,
+        &[],
+        req.amount,
+    ) {
+        Ok(ix) => ix,
+        Err(e) => {
+            return ResponseJson(ApiResponse::error(ApiError::InstructionBuild {
+                reason: "mint_to",
+                source: Box::new(e),
+            }))
+        }
+    };
 
-    // Mock instruction data for MintTo
-    let mut instruction_data = vec![7]; // MintTo instruction index
-    instruction_data.extend_from_slice(&req.amount.to_le_bytes());
-    
-    let response = InstructionResponse {
-        program_id: SPL_TOKEN_PROGRAM_ID.to_string(),
-        accounts,
-        instruction_data: BASE64.encode(instruction_data),
+    ResponseJson(ApiResponse::success(instruction_to_response(ix)))
+}
+
+pub async fn create_nft(
+    payload: Result<Json<CreateNftRequest>, axum::extract::rejection::JsonRejection>,
+) -> ResponseJson<ApiResponse<CreateNftResponse>> {
+    let req = match payload {
+        Ok(Json(req)) => req,
+        Err(_) => return ResponseJson(ApiResponse::error(ApiError::MalformedBody)),
+    };
+    // Validate inputs
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "mint",
+                value: req.mint,
+            }))
+        }
     };
 
-    ResponseJson(ApiResponse::success(response))
+    let mint_authority = match Pubkey::from_str(&req.mint_authority) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                // Matches the wire field name ("mintAuthority"), not the
+                // Rust field name, so callers can map the error back to
+                // the key they actually sent.
+                field: "mintAuthority",
+                value: req.mint_authority,
+            }))
+        }
+    };
+
+    let owner = match Pubkey::from_str(&req.owner) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "owner",
+                value: req.owner,
+            }))
+        }
+    };
+
+    let metadata_program_id =
+        Pubkey::from_str(METADATA_PROGRAM_ID).expect("valid metadata program id");
+
+    let owner_ata = derive_associated_token_account(&owner, &mint);
+    let metadata = derive_metadata_account(&metadata_program_id, &mint);
+    let master_edition = derive_master_edition_account(&metadata_program_id, &mint);
+
+    // Initialize the mint with zero decimals and a supply of one -- a
+    // one-of-one NFT rather than a fungible token.
+    let initialize_mint_ix = match spl_token::instruction::initialize_mint(
+        &spl_token::id(),
+        &mint,
+        &mint_authority,
+        Some(&mint_authority),
+        0,
+    ) {
+        Ok(ix) => ix,
+        Err(e) => {
+            return ResponseJson(ApiResponse::error(ApiError::InstructionBuild {
+                reason: "initialize_mint",
+                source: Box::new(e),
+            }))
+        }
+    };
+
+    let create_ata_ix = spl_associated_token_account::instruction::create_associated_token_account(
+        &mint_authority,
+        &owner,
+        &mint,
+        &spl_token::id(),
+    );
+
+    let mint_to_ix = match spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &mint,
+        &owner_ata,
+        &mint_authority,
+        &[],
+        1,
+    ) {
+        Ok(ix) => ix,
+        Err(e) => {
+            return ResponseJson(ApiResponse::error(ApiError::InstructionBuild {
+                reason: "mint_to",
+                source: Box::new(e),
+            }))
+        }
+    };
+
+    let create_metadata_ix = mpl_token_metadata::instruction::create_metadata_accounts_v3(
+        metadata_program_id,
+        metadata,
+        mint,
+        mint_authority,
+        mint_authority,
+        mint_authority,
+        req.name,
+        req.symbol,
+        req.uri,
+        None,
+        req.seller_fee_basis_points,
+        true,
+        true,
+        None,
+        None,
+        None,
+    );
+
+    // `max_supply: Some(0)` marks this as a master edition with no further
+    // print editions, the standard config for a one-of-one.
+    let create_master_edition_ix = mpl_token_metadata::instruction::create_master_edition_v3(
+        metadata_program_id,
+        master_edition,
+        mint,
+        mint_authority,
+        mint_authority,
+        metadata,
+        mint_authority,
+        Some(0),
+    );
+
+    let instructions = vec![
+        initialize_mint_ix,
+        create_ata_ix,
+        mint_to_ix,
+        create_metadata_ix,
+        create_master_edition_ix,
+    ]
+    .into_iter()
+    .map(instruction_to_response)
+    .collect();
+
+    ResponseJson(ApiResponse::success(CreateNftResponse { instructions }))
 }
 
 pub async fn sign_message(
@@ -127,16 +381,20 @@ pub async fn sign_message(
 ) -> ResponseJson<ApiResponse<SignMessageResponse>> {
     let req = match payload {
         Ok(Json(req)) => req,
-        Err(_) => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
+        Err(_) => return ResponseJson(ApiResponse::error(ApiError::MalformedBody)),
     };
     // Validate and decode secret key
     let secret_bytes = match bs58::decode(&req.secret).into_vec() {
         Ok(bytes) => bytes,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid secret key format".to_string())),
+        Err(e) => {
+            return ResponseJson(ApiResponse::error(ApiError::SecretDecode {
+                source: Box::new(e),
+            }))
+        }
     };
 
     if secret_bytes.len() != 32 {
-        return ResponseJson(ApiResponse::error("Invalid secret key length".to_string()));
+        return ResponseJson(ApiResponse::error(ApiError::InvalidSecretLength));
     }
 
     let mut secret_array = [0u8; 32];
@@ -158,21 +416,90 @@ pub async fn sign_message(
     ResponseJson(ApiResponse::success(response))
 }
 
+pub async fn keystore_import(
+    State(state): State<AppState>,
+    payload: Result<Json<ImportKeyRequest>, axum::extract::rejection::JsonRejection>,
+) -> ResponseJson<ApiResponse<ImportKeyResponse>> {
+    let req = match payload {
+        Ok(Json(req)) => req,
+        Err(_) => return ResponseJson(ApiResponse::error(ApiError::MalformedBody)),
+    };
+    // Validate and decode secret key
+    let secret_bytes = match bs58::decode(&req.secret).into_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return ResponseJson(ApiResponse::error(ApiError::SecretDecode {
+                source: Box::new(e),
+            }))
+        }
+    };
+
+    if secret_bytes.len() != 32 {
+        return ResponseJson(ApiResponse::error(ApiError::InvalidSecretLength));
+    }
+
+    let mut secret_array = [0u8; 32];
+    secret_array.copy_from_slice(&secret_bytes);
+    let signing_key = SigningKey::from_bytes(&secret_array);
+
+    let pubkey = state.keystore.import(signing_key).await;
+    ResponseJson(ApiResponse::success(ImportKeyResponse { pubkey }))
+}
+
+pub async fn keystore_generate(
+    State(state): State<AppState>,
+) -> ResponseJson<ApiResponse<ImportKeyResponse>> {
+    let signing_key = SigningKey::generate(&mut OsRng);
+    let pubkey = state.keystore.import(signing_key).await;
+    ResponseJson(ApiResponse::success(ImportKeyResponse { pubkey }))
+}
+
+pub async fn sign_message_by_id(
+    State(state): State<AppState>,
+    payload: Result<Json<SignByIdRequest>, axum::extract::rejection::JsonRejection>,
+) -> ResponseJson<ApiResponse<SignMessageResponse>> {
+    let req = match payload {
+        Ok(Json(req)) => req,
+        Err(_) => return ResponseJson(ApiResponse::error(ApiError::MalformedBody)),
+    };
+
+    let signature = match state.keystore.sign(&req.pubkey, req.message.as_bytes()).await {
+        Some(signature) => signature,
+        None => return ResponseJson(ApiResponse::error(ApiError::UnknownKeyId)),
+    };
+
+    let response = SignMessageResponse {
+        signature: BASE64.encode(signature.to_bytes()),
+        public_key: req.pubkey,
+        message: req.message,
+    };
+
+    ResponseJson(ApiResponse::success(response))
+}
+
 pub async fn verify_message(
     payload: Result<Json<VerifyMessageRequest>, axum::extract::rejection::JsonRejection>,
 ) -> ResponseJson<ApiResponse<VerifyMessageResponse>> {
     let req = match payload {
         Ok(Json(req)) => req,
-        Err(_) => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
+        Err(_) => return ResponseJson(ApiResponse::error(ApiError::MalformedBody)),
     };
     // Validate and decode public key
     let pubkey_bytes = match bs58::decode(&req.pubkey).into_vec() {
         Ok(bytes) => bytes,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid public key format".to_string())),
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "pubkey",
+                value: req.pubkey,
+            }))
+        }
     };
 
     if pubkey_bytes.len() != 32 {
-        return ResponseJson(ApiResponse::error("Invalid public key length".to_string()));
+        return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+            field: "pubkey",
+            value: req.pubkey,
+        }));
     }
 
     let mut pubkey_array = [0u8; 32];
@@ -180,17 +507,26 @@ pub async fn verify_message(
 
     let verifying_key = match VerifyingKey::from_bytes(&pubkey_array) {
         Ok(vk) => vk,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid public key".to_string())),
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "pubkey",
+                value: req.pubkey,
+            }))
+        }
     };
 
     // Decode signature
     let signature_bytes = match BASE64.decode(&req.signature) {
         Ok(bytes) => bytes,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid signature format".to_string())),
+        Err(e) => {
+            return ResponseJson(ApiResponse::error(ApiError::SignatureDecode {
+                source: Some(Box::new(e)),
+            }))
+        }
     };
 
     if signature_bytes.len() != 64 {
-        return ResponseJson(ApiResponse::error("Invalid signature length".to_string()));
+        return ResponseJson(ApiResponse::error(ApiError::SignatureDecode { source: None }));
     }
 
     let mut signature_array = [0u8; 64];
@@ -216,21 +552,31 @@ pub async fn send_sol(
 ) -> ResponseJson<ApiResponse<InstructionResponse>> {
     let req = match payload {
         Ok(Json(req)) => req,
-        Err(_) => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
+        Err(_) => return ResponseJson(ApiResponse::error(ApiError::MalformedBody)),
     };
     // Validate inputs
     let from = match Pubkey::from_str(&req.from) {
         Ok(pk) => pk,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid sender public key".to_string())),
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "from",
+                value: req.from,
+            }))
+        }
     };
-    
+
     let to = match Pubkey::from_str(&req.to) {
         Ok(pk) => pk,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid recipient public key".to_string())),
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "to",
+                value: req.to,
+            }))
+        }
     };
 
     if req.lamports == 0 {
-        return ResponseJson(ApiResponse::error("Amount must be greater than 0".to_string()));
+        return ResponseJson(ApiResponse::error(ApiError::ZeroAmount));
     }
 
     // Create transfer instruction accounts
@@ -265,59 +611,242 @@ pub async fn send_token(
 ) -> ResponseJson<ApiResponse<InstructionResponse>> {
     let req = match payload {
         Ok(Json(req)) => req,
-        Err(_) => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
+        Err(_) => return ResponseJson(ApiResponse::error(ApiError::MalformedBody)),
     };
     // Validate inputs
     let destination = match Pubkey::from_str(&req.destination) {
         Ok(pk) => pk,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid destination public key".to_string())),
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "destination",
+                value: req.destination,
+            }))
+        }
     };
-    
-    let _mint = match Pubkey::from_str(&req.mint) {
+
+    let mint = match Pubkey::from_str(&req.mint) {
         Ok(pk) => pk,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid mint public key".to_string())),
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "mint",
+                value: req.mint,
+            }))
+        }
     };
-    
+
     let owner = match Pubkey::from_str(&req.owner) {
         Ok(pk) => pk,
-        Err(_) => return ResponseJson(ApiResponse::error("Invalid owner public key".to_string())),
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "owner",
+                value: req.owner,
+            }))
+        }
     };
 
     if req.amount == 0 {
-        return ResponseJson(ApiResponse::error("Amount must be greater than 0".to_string()));
+        return ResponseJson(ApiResponse::error(ApiError::ZeroAmount));
     }
 
-    // For SPL token transfers, we need source token account
-    // This is a simplified mock - in reality you'd derive the associated token account
-    let source_token_account = format!("{}Source", owner);
+    // Derive the owner's and destination's associated token accounts rather
+    // than guessing an address, so the transfer actually touches real accounts.
+    let source_account = derive_associated_token_account(&owner, &mint);
+    let destination_account = derive_associated_token_account(&destination, &mint);
+
+    let ix = match spl_token::instruction::transfer_checked(
+        &spl_token::id(),
+        &source_account,
+        &mint,
+        &destination_account,
+        &owner,
+        &[],
+        req.amount,
+        req.decimals,
+    ) {
+        Ok(ix) => ix,
+        Err(e) => {
+            return ResponseJson(ApiResponse::error(ApiError::InstructionBuild {
+                reason: "transfer_checked",
+                source: Box::new(e),
+            }))
+        }
+    };
 
-    let accounts = vec![
-        AccountMeta {
-            pubkey: source_token_account,
-            is_signer: false,
-            is_writable: true,
-        },
-        AccountMeta {
-            pubkey: destination.to_string(),
-            is_signer: false,
-            is_writable: true,
-        },
-        AccountMeta {
-            pubkey: owner.to_string(),
-            is_signer: true,
-            is_writable: false,
-        },
-    ];
+    ResponseJson(ApiResponse::success(instruction_to_response(ix)))
+}
+
+pub async fn build_transaction(
+    State(state): State<AppState>,
+    payload: Result<Json<BuildTransactionRequest>, axum::extract::rejection::JsonRejection>,
+) -> ResponseJson<ApiResponse<BuildTransactionResponse>> {
+    let req = match payload {
+        Ok(Json(req)) => req,
+        Err(_) => return ResponseJson(ApiResponse::error(ApiError::MalformedBody)),
+    };
+    // Validate inputs
+    let fee_payer = match Pubkey::from_str(&req.fee_payer) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                field: "fee_payer",
+                value: req.fee_payer,
+            }))
+        }
+    };
 
-    // Mock instruction data for Transfer
-    let mut instruction_data = vec![3]; // Transfer instruction index
-    instruction_data.extend_from_slice(&req.amount.to_le_bytes());
+    let blockhash = match Hash::from_str(&req.recent_blockhash) {
+        Ok(hash) => hash,
+        Err(_) => {
+            return ResponseJson(ApiResponse::error(ApiError::InvalidBlockhash {
+                value: req.recent_blockhash,
+            }))
+        }
+    };
 
-    let response = InstructionResponse {
-        program_id: SPL_TOKEN_PROGRAM_ID.to_string(),
-        accounts,
-        instruction_data: BASE64.encode(instruction_data),
+    let instructions = match req
+        .instructions
+        .into_iter()
+        .map(instruction_response_to_instruction)
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(instructions) => instructions,
+        Err(err) => return ResponseJson(ApiResponse::error(err)),
+    };
+
+    let message = Message::new_with_blockhash(&instructions, Some(&fee_payer), &blockhash);
+    let message_bytes = message.serialize();
+    let mut transaction = Transaction::new_unsigned(message.clone());
+
+    // Sign with whichever signers were supplied -- a raw secret or a
+    // previously imported keystore id -- placing each signature at the
+    // position its pubkey occupies in the message's account list.
+    for signer in req.signers {
+        let (signer_pubkey, signature) = match signer {
+            TransactionSigner::Secret { secret } => {
+                let secret_bytes = match bs58::decode(&secret).into_vec() {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        return ResponseJson(ApiResponse::error(ApiError::SecretDecode {
+                            source: Box::new(e),
+                        }))
+                    }
+                };
+                if secret_bytes.len() != 32 {
+                    return ResponseJson(ApiResponse::error(ApiError::InvalidSecretLength));
+                }
+                let mut secret_array = [0u8; 32];
+                secret_array.copy_from_slice(&secret_bytes);
+                let signing_key = SigningKey::from_bytes(&secret_array);
+
+                let signature = signing_key.sign(&message_bytes);
+                let signer_pubkey = Pubkey::new_from_array(signing_key.verifying_key().to_bytes());
+                (signer_pubkey, SolanaSignature::from(signature.to_bytes()))
+            }
+            TransactionSigner::KeystoreId { keystore_id } => {
+                let signer_pubkey = match Pubkey::from_str(&keystore_id) {
+                    Ok(pk) => pk,
+                    Err(_) => {
+                        return ResponseJson(ApiResponse::error(ApiError::InvalidPubkey {
+                            field: "keystore_id",
+                            value: keystore_id,
+                        }))
+                    }
+                };
+                let signature = match state.keystore.sign(&keystore_id, &message_bytes).await {
+                    Some(signature) => signature,
+                    None => return ResponseJson(ApiResponse::error(ApiError::UnknownKeyId)),
+                };
+                (signer_pubkey, SolanaSignature::from(signature.to_bytes()))
+            }
+        };
+
+        match find_signer_index(&message, &signer_pubkey) {
+            Some(index) => transaction.signatures[index] = signature,
+            // The key itself resolved fine (raw secret or keystore lookup) --
+            // it just isn't one of this message's required signers, which is
+            // a distinct failure from "no key stored for that pubkey".
+            None => {
+                return ResponseJson(ApiResponse::error(ApiError::SignerNotRequired {
+                    pubkey: signer_pubkey.to_string(),
+                }))
+            }
+        }
+    }
+
+    let response = BuildTransactionResponse {
+        transaction: BASE64.encode(bincode::serialize(&transaction).expect("transaction should serialize")),
+        message: BASE64.encode(message_bytes),
     };
 
     ResponseJson(ApiResponse::success(response))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_the_same_ata_as_the_spl_helper() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let ours = derive_associated_token_account(&owner, &mint);
+        let reference = spl_associated_token_account::get_associated_token_address(&owner, &mint);
+
+        assert_eq!(ours, reference);
+    }
+
+    #[test]
+    fn metadata_and_master_edition_pdas_use_the_expected_seeds() {
+        let metadata_program_id =
+            Pubkey::from_str(METADATA_PROGRAM_ID).expect("valid metadata program id");
+        let mint = Pubkey::new_unique();
+
+        let metadata = derive_metadata_account(&metadata_program_id, &mint);
+        let (expected_metadata, _) = Pubkey::find_program_address(
+            &[b"metadata", metadata_program_id.as_ref(), mint.as_ref()],
+            &metadata_program_id,
+        );
+        assert_eq!(metadata, expected_metadata);
+
+        let master_edition = derive_master_edition_account(&metadata_program_id, &mint);
+        let (expected_master_edition, _) = Pubkey::find_program_address(
+            &[
+                b"metadata",
+                metadata_program_id.as_ref(),
+                mint.as_ref(),
+                b"edition",
+            ],
+            &metadata_program_id,
+        );
+        assert_eq!(master_edition, expected_master_edition);
+
+        // The two PDAs must differ -- a regression here would mean the
+        // master edition account silently collides with the metadata account.
+        assert_ne!(metadata, master_edition);
+    }
+
+    #[test]
+    fn find_signer_index_locates_fee_payer_and_rejects_non_signers() {
+        let fee_payer = Pubkey::new_unique();
+        let other_signer = Pubkey::new_unique();
+        let readonly_account = Pubkey::new_unique();
+        let not_in_message = Pubkey::new_unique();
+
+        let ix = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![
+                solana_sdk::instruction::AccountMeta::new(other_signer, true),
+                solana_sdk::instruction::AccountMeta::new_readonly(readonly_account, false),
+            ],
+            data: vec![],
+        };
+        let message =
+            Message::new_with_blockhash(&[ix], Some(&fee_payer), &Hash::default());
+
+        assert_eq!(find_signer_index(&message, &fee_payer), Some(0));
+        assert_eq!(find_signer_index(&message, &other_signer), Some(1));
+        assert_eq!(find_signer_index(&message, &readonly_account), None);
+        assert_eq!(find_signer_index(&message, &not_in_message), None);
+    }
+}
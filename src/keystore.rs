@@ -0,0 +1,76 @@
+use ed25519_dalek::{Signature, Signer, SigningKey};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::auth::OidcConfig;
+
+// Shared server state, threaded through handlers via axum's `State` extractor.
+#[derive(Clone, Default)]
+pub struct AppState {
+    pub keystore: Keystore,
+    pub oidc: Option<Arc<OidcConfig>>,
+}
+
+impl AppState {
+    pub fn new(oidc: OidcConfig) -> Self {
+        Self {
+            keystore: Keystore::default(),
+            oidc: Some(Arc::new(oidc)),
+        }
+    }
+}
+
+// A remote signer: secrets are imported or generated once and kept server-side,
+// referenced afterward by their derived pubkey so callers never resend key
+// material. Entries drop their `SigningKey` (and with it its key bytes) when
+// evicted, since `SigningKey` zeroizes itself on drop.
+#[derive(Clone, Default)]
+pub struct Keystore {
+    keys: Arc<RwLock<HashMap<String, SigningKey>>>,
+}
+
+impl Keystore {
+    // Stores `signing_key` under its derived pubkey, returning that pubkey.
+    pub async fn import(&self, signing_key: SigningKey) -> String {
+        let pubkey = bs58::encode(signing_key.verifying_key().as_bytes()).into_string();
+        self.keys.write().await.insert(pubkey.clone(), signing_key);
+        pubkey
+    }
+
+    // Signs `message` with the key stored under `pubkey`, if any.
+    pub async fn sign(&self, pubkey: &str, message: &[u8]) -> Option<Signature> {
+        self.keys.read().await.get(pubkey).map(|key| key.sign(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::Verifier;
+    use rand::rngs::OsRng;
+
+    #[tokio::test]
+    async fn import_then_sign_round_trips_and_verifies() {
+        let keystore = Keystore::default();
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let verifying_key = signing_key.verifying_key();
+
+        let pubkey = keystore.import(signing_key).await;
+        let message = b"hello from the keystore";
+
+        let signature = keystore
+            .sign(&pubkey, message)
+            .await
+            .expect("key was just imported");
+
+        assert!(verifying_key.verify(message, &signature).is_ok());
+    }
+
+    #[tokio::test]
+    async fn sign_returns_none_for_an_unknown_pubkey() {
+        let keystore = Keystore::default();
+
+        assert!(keystore.sign("not-a-stored-key", b"message").await.is_none());
+    }
+}
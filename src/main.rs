@@ -1,4 +1,5 @@
 use axum::{
+    middleware,
     response::Json as ResponseJson,
     routing::{get, post},
     Router,
@@ -6,24 +7,59 @@ use axum::{
 use std::net::SocketAddr;
 use tokio::net::TcpListener;
 
+mod auth;
+mod errors;
 mod handlers;
+mod keystore;
 mod types;
 
+use auth::{require_bearer_auth, OidcConfig};
 use handlers::*;
+use keystore::AppState;
 use types::*;
 
 #[tokio::main]
 async fn main() {
+    let oidc_issuer = std::env::var("OIDC_ISSUER_URL").expect("OIDC_ISSUER_URL must be set");
+    let oidc_audience = std::env::var("OIDC_AUDIENCE").expect("OIDC_AUDIENCE must be set");
+    let oidc = OidcConfig::discover(oidc_issuer, oidc_audience)
+        .await
+        .expect("failed to fetch OIDC JWKS from issuer");
+
+    let state = AppState::new(oidc);
+
+    // Signing, minting, and transfer endpoints require a valid bearer token;
+    // keypair generation and verification stay public.
+    let protected = Router::new()
+        .route("/message/sign", post(sign_message))
+        .route("/token/mint", post(mint_token))
+        .route("/send/sol", post(send_sol))
+        .route("/send/token", post(send_token))
+        // Builds and signs real transactions, so it's just as sensitive as
+        // the raw signing/transfer routes above.
+        .route("/transaction/build", post(build_transaction))
+        // The keystore has no per-caller ownership model, so importing,
+        // generating, or signing by id must all sit behind auth too --
+        // otherwise any caller can sign with a key someone else imported.
+        .route("/message/sign/by-id", post(sign_message_by_id))
+        .route("/keystore/import", post(keystore_import))
+        .route("/keystore/generate", post(keystore_generate))
+        // Builds a MintTo instruction (among others) just like /token/mint,
+        // so it needs the same protection.
+        .route("/nft/create", post(create_nft))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_bearer_auth,
+        ));
+
     // Build the router with all endpoints
     let app = Router::new()
         .route("/", get(health_check))
         .route("/keypair", post(generate_keypair))
         .route("/token/create", post(create_token))
-        .route("/token/mint", post(mint_token))
-        .route("/message/sign", post(sign_message))
         .route("/message/verify", post(verify_message))
-        .route("/send/sol", post(send_sol))
-        .route("/send/token", post(send_token));
+        .merge(protected)
+        .with_state(state);
 
     // Start the server
     let port = std::env::var("PORT")
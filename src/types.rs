@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::errors::ApiError;
+
 // Standard API Response format
 #[derive(Debug, Serialize)]
 pub struct ApiResponse<T> {
@@ -8,6 +10,8 @@ pub struct ApiResponse<T> {
     pub data: Option<T>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
 }
 
 impl<T> ApiResponse<T> {
@@ -16,14 +20,16 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            error_code: None,
         }
     }
 
-    pub fn error(error: String) -> Self {
+    pub fn error(err: ApiError) -> Self {
         Self {
             success: false,
             data: None,
-            error: Some(error),
+            error_code: Some(err.code().to_string()),
+            error: Some(err.to_string()),
         }
     }
 }
@@ -60,6 +66,25 @@ pub struct SignMessageRequest {
     pub secret: String,
 }
 
+// Keystore import request
+#[derive(Debug, Deserialize)]
+pub struct ImportKeyRequest {
+    pub secret: String,
+}
+
+// Keystore import/generate response
+#[derive(Debug, Serialize)]
+pub struct ImportKeyResponse {
+    pub pubkey: String,
+}
+
+// Sign-by-id request: signs with a key already held in the keystore
+#[derive(Debug, Deserialize)]
+pub struct SignByIdRequest {
+    pub pubkey: String,
+    pub message: String,
+}
+
 // Message sign response
 #[derive(Debug, Serialize)]
 pub struct SignMessageResponse {
@@ -99,20 +124,67 @@ pub struct SendTokenRequest {
     pub mint: String,
     pub owner: String,
     pub amount: u64,
+    pub decimals: u8,
+}
+
+// NFT create request
+#[derive(Debug, Deserialize)]
+pub struct CreateNftRequest {
+    pub mint: String,
+    #[serde(rename = "mintAuthority")]
+    pub mint_authority: String,
+    pub owner: String,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
 }
 
-// Account meta for instruction responses
+// NFT create response
 #[derive(Debug, Serialize)]
+pub struct CreateNftResponse {
+    pub instructions: Vec<InstructionResponse>,
+}
+
+// Account meta for instruction responses, also reused as the account-meta
+// shape for caller-supplied instructions in transaction/build
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AccountMeta {
     pub pubkey: String,
     pub is_signer: bool,
     pub is_writable: bool,
 }
 
-// Instruction response
-#[derive(Debug, Serialize)]
+// Instruction response, also reused as the instruction spec shape accepted
+// by transaction/build
+#[derive(Debug, Serialize, Deserialize)]
 pub struct InstructionResponse {
     pub program_id: String,
     pub accounts: Vec<AccountMeta>,
     pub instruction_data: String,
 }
+
+// Transaction build request: caller-supplied instructions plus a fee payer
+// and recent blockhash; signers are either raw secrets or a keystore id.
+#[derive(Debug, Deserialize)]
+pub struct BuildTransactionRequest {
+    pub instructions: Vec<InstructionResponse>,
+    pub fee_payer: String,
+    pub recent_blockhash: String,
+    #[serde(default)]
+    pub signers: Vec<TransactionSigner>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum TransactionSigner {
+    Secret { secret: String },
+    KeystoreId { keystore_id: String },
+}
+
+// Transaction build response
+#[derive(Debug, Serialize)]
+pub struct BuildTransactionResponse {
+    pub transaction: String,
+    pub message: String,
+}